@@ -4,6 +4,7 @@
 //! Chat API
 
 use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
 
 use crate::requests::Requests;
 use crate::*;
@@ -20,7 +21,7 @@ where
 }
 
 
-use super::{completions::Completion, CHAT_COMPLETION_CREATE};
+use super::{completions::Completion, completions::LogProbs, CHAT_COMPLETION_CREATE};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ChatBody {
@@ -83,16 +84,159 @@ pub struct ChatBody {
 	/// values like -100 or 100 should result in a ban or exclusive selection of the relevant token.
 	/// Defaults to null
 	#[serde(skip_serializing_if = "Option::is_none")]
-	pub logit_bias: Option<HashMap<String, String>>,
+	pub logit_bias: Option<HashMap<i32, f32>>,
 	/// A unique identifier representing your end-user,
 	/// which can help OpenAI to monitor and detect abuse. Learn more.
 	#[serde(skip_serializing_if = "Option::is_none")]
 	pub user: Option<String>,
+	/// A list of functions the model may generate JSON inputs for.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub functions: Option<Vec<Function>>,
+	/// This feature is in Beta.
+	/// If specified, our system will make a best effort to sample deterministically,
+	/// such that repeated requests with the same `seed` and parameters should return the same result.
+	/// Determinism is not guaranteed, and you should refer to the `system_fingerprint` response
+	/// parameter to monitor changes in the backend.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub seed: Option<i32>,
+	/// Whether to return log probabilities of the output tokens.
+	/// If true, returns the log probabilities of each output token on the `logprobs` field of each choice.
+	/// Defaults to false
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub logprobs: Option<bool>,
+	/// An integer between 0 and 20 specifying the number of most likely tokens to return
+	/// at each token position, each with an associated log probability.
+	/// `logprobs` must be set to `true` if this parameter is used.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub top_logprobs: Option<i32>,
+	/// Controls how the model responds to function calls.
+	/// `"none"` means the model will not call a function and instead generates a message.
+	/// `"auto"` means the model can pick between generating a message or calling a function.
+	/// Specifying a particular function via `{"name": "my_function"}` forces the model to call that function.
+	/// `"none"` is the default when no functions are present; `"auto"` is the default if functions are present.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub function_call: Option<FunctionCallSetting>,
+}
+
+/// A function the model may choose to call, described as a JSON-Schema object.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Function {
+	pub name: String,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub description: Option<String>,
+	pub parameters: serde_json::Value,
+}
+
+/// Controls whether and which function the model should call.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FunctionCallSetting {
+	Mode(FunctionCallMode),
+	Force { name: String },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FunctionCallMode {
+	None,
+	Auto,
+}
+
+/// A function call the model chose to make, returned on a `Message` instead of content.
+/// `arguments` is a JSON string and is not guaranteed to be valid JSON-Schema-conformant output;
+/// callers should validate it before use.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionCall {
+	pub name: String,
+	pub arguments: String,
+}
+
+/// A single server-sent-events chunk of a streaming chat completion.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChatDelta {
+	pub id: String,
+	pub object: String,
+	pub created: i64,
+	pub model: String,
+	pub choices: Vec<DeltaChoice>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeltaChoice {
+	pub index: i32,
+	pub delta: Delta,
+	pub finish_reason: Option<String>,
+	/// Log probability information, present when the request set `logprobs: true`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub logprobs: Option<LogProbs>,
+}
+
+/// A partial message fragment. `role` is only present on the first chunk of a
+/// choice, and `content` may be absent entirely (e.g. on the final chunk).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Delta {
+	pub role: Option<Role>,
+	pub content: Option<String>,
+	/// Set when the model is calling a function instead of replying with text.
+	/// `name` and `arguments` arrive incrementally across chunks, so both are optional;
+	/// concatenate the `arguments` fragments across the stream to recover the full JSON.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub function_call: Option<FunctionCallDelta>,
+}
+
+/// A partial function call fragment streamed on a `Delta`. See `FunctionCall` for the
+/// fully-assembled shape returned by non-streaming completions.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FunctionCallDelta {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub arguments: Option<String>,
+}
+
+/// Iterator over the server-sent-events stream of a streaming chat completion.
+/// Yields one `ChatDelta` per non-empty `data: ` line until the API sends `[DONE]`.
+/// Generic over the underlying reader so the SSE parsing can be exercised against an
+/// in-memory buffer in tests instead of a live `reqwest::blocking::Response`.
+pub struct ChatCompletionStream<R: std::io::Read = reqwest::blocking::Response> {
+	lines: std::io::Lines<BufReader<R>>,
+}
+
+impl<R: std::io::Read> Iterator for ChatCompletionStream<R> {
+	type Item = ApiResult<ChatDelta>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		loop {
+			let line = match self.lines.next()? {
+				Ok(line) => line,
+				Err(e) => return Some(Err(ApiError { message: e.to_string(), code: None })),
+			};
+			let line = line.trim();
+			if line.is_empty() {
+				continue;
+			}
+			let payload = match line.strip_prefix("data: ") {
+				Some(payload) => payload,
+				None => continue,
+			};
+			if payload == "[DONE]" {
+				return None;
+			}
+			return Some(
+				serde_json::from_str(payload)
+					.map_err(|e| ApiError { message: e.to_string(), code: None }),
+			);
+		}
+	}
 }
 
 pub trait ChatApi {
 	/// Creates a completion for the chat message
 	fn chat_completion_create(&self, chat_body: &ChatBody) -> ApiResult<Completion>;
+	/// Creates a completion for the chat message, streaming back partial message
+	/// deltas as they are generated instead of waiting for the full response.
+	/// `chat_body.stream` is forced to `Some(true)` regardless of the value passed in.
+	fn chat_completion_create_stream(&self, chat_body: &ChatBody) -> ApiResult<ChatCompletionStream>;
 }
 
 impl ChatApi for OpenAI {
@@ -102,13 +246,174 @@ impl ChatApi for OpenAI {
 		let completion: Completion = serde_json::from_value(res.clone()).unwrap();
 		Ok(completion)
 	}
+
+	fn chat_completion_create_stream(&self, chat_body: &ChatBody) -> ApiResult<ChatCompletionStream> {
+		let mut chat_body = serde_json::to_value(chat_body).unwrap();
+		chat_body["stream"] = serde_json::Value::Bool(true);
+		let res = self.post_stream(CHAT_COMPLETION_CREATE, chat_body)?;
+		Ok(ChatCompletionStream { lines: BufReader::new(res).lines() })
+	}
 }
 
 #[cfg(test)]
 mod tests {
-	use crate::{apis::chat::ChatBody, openai::new_test_openai, Message, Role};
+	use std::collections::HashMap;
+	use std::io::{BufRead, BufReader, Cursor};
+
+	use crate::{apis::chat::ChatBody, apis::completions::Completion, openai::new_test_openai, Message, Role};
 
-	use super::ChatApi;
+	use super::{ChatApi, ChatCompletionStream, FunctionCallMode, FunctionCallSetting};
+
+	fn stream_from_bytes(data: &'static [u8]) -> ChatCompletionStream<Cursor<&'static [u8]>> {
+		ChatCompletionStream { lines: BufReader::new(Cursor::new(data)).lines() }
+	}
+
+	#[test]
+	fn stream_yields_one_delta_per_data_line_and_stops_on_done() {
+		let stream = stream_from_bytes(
+			b"data: {\"id\":\"1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"gpt-4\",\"choices\":[]}\n\n\
+			  data: {\"id\":\"2\",\"object\":\"chat.completion.chunk\",\"created\":2,\"model\":\"gpt-4\",\"choices\":[]}\n\n\
+			  data: [DONE]\n",
+		);
+		let deltas: Vec<_> = stream.map(|d| d.unwrap().id).collect();
+		assert_eq!(deltas, vec!["1".to_string(), "2".to_string()]);
+	}
+
+	#[test]
+	fn stream_skips_blank_lines_and_non_data_lines() {
+		let stream = stream_from_bytes(
+			b"event: ping\n\n\
+			  \n\
+			  data: {\"id\":\"1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"gpt-4\",\"choices\":[]}\n\n\
+			  data: [DONE]\n",
+		);
+		let deltas: Vec<_> = stream.map(|d| d.unwrap().id).collect();
+		assert_eq!(deltas, vec!["1".to_string()]);
+	}
+
+	#[test]
+	fn stream_surfaces_malformed_json_as_api_error() {
+		let mut stream = stream_from_bytes(b"data: not json\n\ndata: [DONE]\n");
+		assert!(stream.next().unwrap().is_err());
+	}
+
+	#[test]
+	fn stream_carries_incremental_function_call_fragments() {
+		let mut stream = stream_from_bytes(
+			b"data: {\"id\":\"1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"gpt-4\",\
+			  \"choices\":[{\"index\":0,\"delta\":{\"function_call\":{\"name\":\"get_weather\"}},\"finish_reason\":null}]}\n\n\
+			  data: {\"id\":\"1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"gpt-4\",\
+			  \"choices\":[{\"index\":0,\"delta\":{\"function_call\":{\"arguments\":\"{\\\"city\\\":\\\"Paris\\\"}\"}},\"finish_reason\":null}]}\n\n\
+			  data: [DONE]\n",
+		);
+		let first = stream.next().unwrap().unwrap();
+		let first_call = first.choices[0].delta.function_call.as_ref().unwrap();
+		assert_eq!(first_call.name.as_deref(), Some("get_weather"));
+		assert!(first_call.arguments.is_none());
+
+		let second = stream.next().unwrap().unwrap();
+		let second_call = second.choices[0].delta.function_call.as_ref().unwrap();
+		assert!(second_call.name.is_none());
+		assert_eq!(second_call.arguments.as_deref(), Some("{\"city\":\"Paris\"}"));
+	}
+
+	#[test]
+	fn stream_carries_per_chunk_logprobs() {
+		let mut stream = stream_from_bytes(
+			b"data: {\"id\":\"1\",\"object\":\"chat.completion.chunk\",\"created\":1,\"model\":\"gpt-4\",\
+			  \"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"},\"finish_reason\":null,\
+			  \"logprobs\":{\"content\":[{\"token\":\"Hi\",\"logprob\":-0.1,\"top_logprobs\":[]}]}}]}\n\n\
+			  data: [DONE]\n",
+		);
+		let chunk = stream.next().unwrap().unwrap();
+		let logprobs = chunk.choices[0].logprobs.as_ref().unwrap();
+		assert_eq!(logprobs.content.as_ref().unwrap()[0].token, "Hi");
+	}
+
+	#[test]
+	fn seed_is_omitted_when_none_and_serialized_when_set() {
+		let without_seed = ChatBody {
+			model: "gpt-3.5-turbo".to_string(),
+			max_tokens: None,
+			temperature: None,
+			top_p: None,
+			n: None,
+			stream: None,
+			stop: None,
+			presence_penalty: None,
+			frequency_penalty: None,
+			logit_bias: None,
+			user: None,
+			seed: None,
+			logprobs: None,
+			top_logprobs: None,
+			functions: None,
+			function_call: None,
+			messages: vec![],
+		};
+		assert!(serde_json::to_value(&without_seed).unwrap().get("seed").is_none());
+
+		let with_seed = ChatBody { seed: Some(42), ..without_seed };
+		assert_eq!(serde_json::to_value(&with_seed).unwrap()["seed"], serde_json::json!(42));
+	}
+
+	#[test]
+	fn system_fingerprint_round_trips_and_is_omitted_when_absent() {
+		let with_fingerprint = serde_json::json!({
+			"id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "gpt-3.5-turbo",
+			"choices": [], "usage": { "prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2 },
+			"system_fingerprint": "fp_44709d6fcb"
+		});
+		let completion: Completion = serde_json::from_value(with_fingerprint).unwrap();
+		assert_eq!(completion.system_fingerprint.as_deref(), Some("fp_44709d6fcb"));
+		assert!(serde_json::to_value(&completion).unwrap().get("system_fingerprint").is_some());
+
+		let without_fingerprint = serde_json::json!({
+			"id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "gpt-3.5-turbo",
+			"choices": [], "usage": { "prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2 }
+		});
+		let completion: Completion = serde_json::from_value(without_fingerprint).unwrap();
+		assert!(completion.system_fingerprint.is_none());
+		assert!(serde_json::to_value(&completion).unwrap().get("system_fingerprint").is_none());
+	}
+
+	#[test]
+	fn choice_logprobs_content_deserializes_per_token_alternatives() {
+		let body = serde_json::json!({
+			"id": "chatcmpl-1", "object": "chat.completion", "created": 1, "model": "gpt-3.5-turbo",
+			"usage": { "prompt_tokens": 1, "completion_tokens": 1, "total_tokens": 2 },
+			"choices": [{
+				"index": 0,
+				"message": null,
+				"text": null,
+				"finish_reason": "stop",
+				"logprobs": {
+					"content": [{
+						"token": "Hello",
+						"logprob": -0.31,
+						"top_logprobs": [{ "token": "Hello", "logprob": -0.31 }, { "token": "Hi", "logprob": -1.2 }]
+					}]
+				}
+			}]
+		});
+		let completion: Completion = serde_json::from_value(body).unwrap();
+		let logprobs = completion.choices[0].logprobs.as_ref().unwrap();
+		let token = &logprobs.content.as_ref().unwrap()[0];
+		assert_eq!(token.token, "Hello");
+		assert_eq!(token.top_logprobs.len(), 2);
+		assert_eq!(token.top_logprobs[1].token, "Hi");
+	}
+
+	#[test]
+	fn logit_bias_round_trips_keyed_by_token_id() {
+		let mut logit_bias = HashMap::new();
+		logit_bias.insert(15043, -100.0_f32);
+		let value = serde_json::to_value(&logit_bias).unwrap();
+		assert_eq!(value, serde_json::json!({ "15043": -100.0 }));
+
+		let round_tripped: HashMap<i32, f32> = serde_json::from_value(value).unwrap();
+		assert_eq!(round_tripped, logit_bias);
+	}
 
 	#[test]
 	fn test_chat_completion() {
@@ -125,11 +430,72 @@ mod tests {
 			frequency_penalty: None,
 			logit_bias: None,
 			user: None,
-			messages: vec![Message { role: Role::User, content: "Hello!".to_string() }],
+			seed: None,
+			logprobs: None,
+			top_logprobs: None,
+			functions: None,
+			function_call: None,
+			messages: vec![Message {
+				role: Role::User,
+				content: Some("Hello!".to_string()),
+				name: None,
+				function_call: None,
+			}],
 		};
 		let rs = openai.chat_completion_create(&body);
 		let choice = rs.unwrap().choices;
 		let message = &choice[0].message.as_ref().unwrap();
-		assert!(message.content.contains("Hello"));
+		assert!(message.content.as_deref().unwrap().contains("Hello"));
+	}
+
+	#[test]
+	fn function_call_setting_serializes_as_mode_string_or_name_object() {
+		let mode = FunctionCallSetting::Mode(FunctionCallMode::Auto);
+		assert_eq!(serde_json::to_value(&mode).unwrap(), serde_json::json!("auto"));
+
+		let force = FunctionCallSetting::Force { name: "get_weather".to_string() };
+		assert_eq!(serde_json::to_value(&force).unwrap(), serde_json::json!({ "name": "get_weather" }));
+	}
+
+	#[test]
+	fn function_call_setting_round_trips_through_json() {
+		for setting in [
+			FunctionCallSetting::Mode(FunctionCallMode::None),
+			FunctionCallSetting::Mode(FunctionCallMode::Auto),
+			FunctionCallSetting::Force { name: "get_weather".to_string() },
+		] {
+			let value = serde_json::to_value(&setting).unwrap();
+			let round_tripped: FunctionCallSetting = serde_json::from_value(value).unwrap();
+			assert_eq!(serde_json::to_value(&round_tripped).unwrap(), serde_json::to_value(&setting).unwrap());
+		}
+	}
+
+	#[test]
+	fn message_with_function_call_has_no_content() {
+		let body = serde_json::json!({
+			"role": "assistant",
+			"content": null,
+			"function_call": { "name": "get_weather", "arguments": "{\"city\":\"Paris\"}" }
+		});
+		let message: Message = serde_json::from_value(body).unwrap();
+		assert!(message.content.is_none());
+		assert_eq!(message.function_call.unwrap().name, "get_weather");
+	}
+
+	#[test]
+	fn function_result_message_round_trips_with_name() {
+		let message = Message {
+			role: Role::Function,
+			content: Some("{\"temperature\": 72}".to_string()),
+			name: Some("get_weather".to_string()),
+			function_call: None,
+		};
+		let value = serde_json::to_value(&message).unwrap();
+		assert_eq!(value["role"], serde_json::json!("function"));
+		assert_eq!(value["name"], serde_json::json!("get_weather"));
+
+		let round_tripped: Message = serde_json::from_value(value).unwrap();
+		assert_eq!(round_tripped.role, Role::Function);
+		assert_eq!(round_tripped.name.as_deref(), Some("get_weather"));
 	}
 }
@@ -0,0 +1,59 @@
+//! Response types of completion-style endpoints; used by the `chat` API.
+
+use serde::{Deserialize, Serialize};
+
+use crate::Message;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Completion {
+	pub id: String,
+	pub object: String,
+	pub created: i64,
+	pub model: String,
+	pub choices: Vec<Choice>,
+	pub usage: Usage,
+	/// Identifies the backend configuration that generated this completion.
+	/// Can be used alongside the `seed` request parameter to detect backend changes
+	/// that may affect determinism.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub system_fingerprint: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Choice {
+	pub index: i32,
+	pub message: Option<Message>,
+	pub text: Option<String>,
+	pub finish_reason: Option<String>,
+	/// Log probability information, present when the request set `logprobs: true`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub logprobs: Option<LogProbs>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Usage {
+	pub prompt_tokens: i32,
+	pub completion_tokens: Option<i32>,
+	pub total_tokens: i32,
+}
+
+/// Per-token log probability information for a single choice.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LogProbs {
+	pub content: Option<Vec<TokenLogProb>>,
+}
+
+/// The log probability of a single output token, along with the most likely alternatives
+/// at that position (up to `top_logprobs` of them, as requested).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TokenLogProb {
+	pub token: String,
+	pub logprob: f32,
+	pub top_logprobs: Vec<TopLogProb>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TopLogProb {
+	pub token: String,
+	pub logprob: f32,
+}
@@ -0,0 +1,6 @@
+//! OpenAI API endpoints, grouped by resource.
+
+pub mod chat;
+pub mod completions;
+
+pub const CHAT_COMPLETION_CREATE: &str = "chat/completions";
@@ -0,0 +1,54 @@
+//! A Rust client for the OpenAI API.
+
+pub mod apis;
+pub mod openai;
+pub mod requests;
+
+pub use crate::openai::OpenAI;
+
+use serde::{Deserialize, Serialize};
+
+/// The result type returned by every API call in this crate.
+pub type ApiResult<T> = Result<T, ApiError>;
+
+/// An error returned by the OpenAI API, or encountered while talking to it.
+#[derive(Debug)]
+pub struct ApiError {
+	pub message: String,
+	pub code: Option<String>,
+}
+
+impl std::fmt::Display for ApiError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.message)
+	}
+}
+
+impl std::error::Error for ApiError {}
+
+/// A single message in a chat conversation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+	pub role: Role,
+	/// `None` when the model chooses to call a function instead of replying with text;
+	/// see `function_call`.
+	pub content: Option<String>,
+	/// The name of the function whose result this message carries, when `role` is `Function`.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub name: Option<String>,
+	/// Set instead of `content` when the model chooses to call a function.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub function_call: Option<crate::apis::chat::FunctionCall>,
+}
+
+/// The role of the author of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+	System,
+	User,
+	Assistant,
+	/// The result of a function call, fed back into the conversation so the model can use it.
+	/// Requires `name` to be set on the `Message` to identify which function produced it.
+	Function,
+}
@@ -0,0 +1,44 @@
+//! The OpenAI API client.
+
+use std::time::Duration;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// The OpenAI API client. Holds the credentials and endpoint used to authenticate
+/// and route every request made through the `*Api` traits.
+#[derive(Debug, Clone)]
+pub struct OpenAI {
+	pub(crate) api_key: String,
+	pub(crate) api_endpoint: String,
+	/// How many times a request that hit a rate limit or transient server error is retried.
+	pub(crate) max_retries: u32,
+	/// The base delay used for exponential backoff between retries.
+	pub(crate) base_delay: Duration,
+}
+
+impl OpenAI {
+	/// Creates a new client pointed at the default OpenAI API endpoint.
+	pub fn new(api_key: String) -> Self {
+		OpenAI {
+			api_key,
+			api_endpoint: "https://api.openai.com/v1".to_string(),
+			max_retries: DEFAULT_MAX_RETRIES,
+			base_delay: DEFAULT_BASE_DELAY,
+		}
+	}
+
+	/// Overrides the retry behavior applied to rate-limit and transient server errors.
+	/// Defaults to 3 retries with a 500ms base delay.
+	pub fn with_retry(mut self, max_retries: u32, base_delay: Duration) -> Self {
+		self.max_retries = max_retries;
+		self.base_delay = base_delay;
+		self
+	}
+}
+
+/// Builds an `OpenAI` client from the `OPENAI_API_KEY` environment variable, for use in tests.
+pub fn new_test_openai() -> OpenAI {
+	let api_key = std::env::var("OPENAI_API_KEY").expect("OPENAI_API_KEY is not set");
+	OpenAI::new(api_key)
+}
@@ -0,0 +1,146 @@
+//! Low-level HTTP plumbing shared by every API.
+
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::blocking::{Client, Response};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::{openai::OpenAI, ApiError, ApiResult};
+
+pub trait Requests {
+	fn post(&self, sub_url: &str, body: Value) -> ApiResult<Value>;
+	fn get(&self, sub_url: &str) -> ApiResult<Value>;
+	/// Like `post`, but returns the raw response body unread so the caller can stream it
+	/// (e.g. as server-sent events) instead of buffering it into a `Value`. Not retried.
+	fn post_stream(&self, sub_url: &str, body: Value) -> ApiResult<Response>;
+}
+
+impl Requests for OpenAI {
+	fn post(&self, sub_url: &str, body: Value) -> ApiResult<Value> {
+		let url = format!("{}/{}", self.api_endpoint, sub_url);
+		let client = Client::new();
+		for attempt in 0..=self.max_retries {
+			let res = client
+				.post(&url)
+				.bearer_auth(&self.api_key)
+				.json(&body)
+				.send()
+				.map_err(|e| ApiError { message: e.to_string(), code: None })?;
+			if attempt < self.max_retries && is_retryable(res.status()) {
+				std::thread::sleep(retry_delay(res.headers(), self.base_delay, attempt));
+				continue;
+			}
+			return parse_response(res);
+		}
+		unreachable!("loop always returns via parse_response")
+	}
+
+	fn get(&self, sub_url: &str) -> ApiResult<Value> {
+		let url = format!("{}/{}", self.api_endpoint, sub_url);
+		let res = Client::new()
+			.get(&url)
+			.bearer_auth(&self.api_key)
+			.send()
+			.map_err(|e| ApiError { message: e.to_string(), code: None })?;
+		parse_response(res)
+	}
+
+	fn post_stream(&self, sub_url: &str, body: Value) -> ApiResult<Response> {
+		let url = format!("{}/{}", self.api_endpoint, sub_url);
+		let res = Client::new()
+			.post(&url)
+			.bearer_auth(&self.api_key)
+			.json(&body)
+			.send()
+			.map_err(|e| ApiError { message: e.to_string(), code: None })?;
+		if res.status().is_success() {
+			Ok(res)
+		} else {
+			let body: Value = res
+				.json()
+				.map_err(|e| ApiError { message: e.to_string(), code: None })?;
+			Err(api_error_from_body(&body))
+		}
+	}
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+	status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// The delay to wait before the next retry: the `Retry-After` header if the server sent one,
+/// otherwise `base_delay * 2^attempt` plus up to 100ms of jitter to avoid retry storms.
+fn retry_delay(headers: &reqwest::header::HeaderMap, base_delay: Duration, attempt: u32) -> Duration {
+	if let Some(retry_after) = headers
+		.get(reqwest::header::RETRY_AFTER)
+		.and_then(|v| v.to_str().ok())
+		.and_then(|v| v.parse::<u64>().ok())
+	{
+		return Duration::from_secs(retry_after);
+	}
+	let backoff = base_delay.saturating_mul(1 << attempt.min(16));
+	let jitter = Duration::from_millis(rand::thread_rng().gen_range(0..100));
+	backoff + jitter
+}
+
+fn parse_response(res: Response) -> ApiResult<Value> {
+	let status = res.status();
+	let body: Value = res
+		.json()
+		.map_err(|e| ApiError { message: e.to_string(), code: None })?;
+	if status.is_success() {
+		Ok(body)
+	} else {
+		Err(api_error_from_body(&body))
+	}
+}
+
+/// Builds an `ApiError` from an OpenAI-shaped `{"error": {"message": ..., "code": ...}}` body.
+pub(crate) fn api_error_from_body(body: &Value) -> ApiError {
+	ApiError {
+		message: body["error"]["message"]
+			.as_str()
+			.unwrap_or("unknown error")
+			.to_string(),
+		code: body["error"]["code"].as_str().map(|s| s.to_string()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use reqwest::header::HeaderMap;
+
+	use super::*;
+
+	#[test]
+	fn is_retryable_matches_429_and_5xx_only() {
+		assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS));
+		assert!(is_retryable(StatusCode::INTERNAL_SERVER_ERROR));
+		assert!(is_retryable(StatusCode::SERVICE_UNAVAILABLE));
+		assert!(!is_retryable(StatusCode::OK));
+		assert!(!is_retryable(StatusCode::BAD_REQUEST));
+		assert!(!is_retryable(StatusCode::NOT_FOUND));
+	}
+
+	#[test]
+	fn retry_delay_honors_retry_after_header() {
+		let mut headers = HeaderMap::new();
+		headers.insert(reqwest::header::RETRY_AFTER, "2".parse().unwrap());
+		let delay = retry_delay(&headers, Duration::from_millis(50), 0);
+		assert_eq!(delay, Duration::from_secs(2));
+	}
+
+	#[test]
+	fn retry_delay_backs_off_exponentially_without_header() {
+		let headers = HeaderMap::new();
+		let base = Duration::from_millis(100);
+		for (attempt, multiplier) in [(0, 1), (1, 2), (2, 4)] {
+			let delay = retry_delay(&headers, base, attempt);
+			let expected_backoff = base * multiplier;
+			assert!(delay >= expected_backoff);
+			assert!(delay < expected_backoff + Duration::from_millis(100));
+		}
+	}
+}